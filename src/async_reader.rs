@@ -0,0 +1,269 @@
+//! Async flavour of `IndexedLineReader`.
+//!
+//! `AsyncIndexedLineReader` mirrors the blocking `IndexedLineReader`, but drives its
+//! byte seeks and line reads over a reader implementing `AsyncBufRead + AsyncSeek`
+//! (as provided by `futures`/`async-std`) instead of `BufRead + Seek`. The
+//! `LinesIndex` stays plain synchronous data; only the I/O-driving
+//! `compute`/`seek_forward`/`seek_to_index` paths have async equivalents here.
+//!
+//! This module is only compiled when the `async` feature is enabled.
+
+use std::io::{Error, SeekFrom};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures::io::{AsyncBufRead, AsyncBufReadExt, AsyncSeek, AsyncSeekExt};
+
+use crate::LinesIndex;
+
+/// Progress of an in-flight `poll_seek`, allowing the line-indexed seek to be
+/// driven across `Poll::Pending` suspensions without holding a borrow of the
+/// reader across await points.
+#[derive(Debug)]
+enum SeekState {
+    /// No seek in progress; the next `poll_seek` resolves the target line.
+    Idle,
+    /// Awaiting the byte seek to the closest indexed anchor, then skip `remaining` lines.
+    Seeking { byte: u64, remaining: u64 },
+    /// Reading forward from `byte`, skipping the `remaining` lines up to the target.
+    Skipping { byte: u64, remaining: u64 }
+}
+
+/// An async line-indexed reader that seeks to specific lines over an
+/// `AsyncBufRead + AsyncSeek` source.
+#[derive(Debug)]
+pub struct AsyncIndexedLineReader<T> {
+    index: LinesIndex,
+    pos: u64,
+    line_count: u64,
+    seek_state: SeekState,
+    reader: T
+}
+
+impl<T: AsyncBufRead + AsyncSeek + Unpin> AsyncIndexedLineReader<T> {
+    pub fn new(reader: T, index_granularity: u64) -> AsyncIndexedLineReader<T> {
+        AsyncIndexedLineReader {
+            index: LinesIndex::new(index_granularity),
+            pos: 0,
+            line_count: 0,
+            seek_state: SeekState::Idle,
+            reader
+        }
+    }
+
+    pub fn get_index(&self) -> &LinesIndex {
+        &self.index
+    }
+
+    pub fn restore_index(&mut self, index: LinesIndex) {
+        self.index = index;
+    }
+
+    pub fn clear_index(&mut self) {
+        self.index.clear()
+    }
+
+    pub fn get_current_pos(&self) -> u64 {
+        self.pos
+    }
+
+    /// Scans the unindexed tail of the file, awaiting the underlying reader, and
+    /// records a byte count every `granularity` lines. Returns the total line count.
+    pub async fn compute_index(&mut self) -> Result<u64, Error> {
+        let initial_pos = self.index.last_indexed_pos().unwrap_or(0);
+        let mut line_count = initial_pos;
+        let mut byte_count = self.index.byte_count_at_pos(&line_count).unwrap_or(0);
+        self.reader.seek(SeekFrom::Start(byte_count)).await?;
+        let mut pos = 0u64;
+        loop {
+            let mut line = String::new();
+            let read = self.reader.read_line(&mut line).await?;
+            if read == 0 { break }
+            byte_count += read as u64;
+            if (pos + 1).is_multiple_of(self.index.granularity) {
+                self.index.insert(initial_pos + pos + 1, byte_count);
+            }
+            line_count += 1;
+            pos += 1;
+        }
+        self.index.line_count = line_count;
+        self.index.byte_count = byte_count;
+        self.line_count = line_count;
+        Ok(line_count)
+    }
+
+    async fn seek_to_index(&mut self, indexed_pos: u64) -> Result<u64, Error> {
+        self.pos = indexed_pos;
+        let byte_count = self.index.byte_count_at_pos(&indexed_pos).unwrap_or(0);
+        self.reader.seek(SeekFrom::Start(byte_count)).await
+    }
+
+    async fn seek_forward(&mut self, lines: u64) -> Result<u64, Error> {
+        let mut lines_left = lines;
+        let mut extra_byte_count: u64 = 0;
+        while lines_left > 0 {
+            let mut line = String::new();
+            let read = self.reader.read_line(&mut line).await?;
+            if read == 0 { break }
+            lines_left -= 1;
+            self.pos += 1;
+            extra_byte_count += read as u64;
+        }
+        Ok(extra_byte_count)
+    }
+
+    async fn seek_to_closest_index(&mut self, pos: SeekFrom) -> Result<u64, Error> {
+        match pos {
+            SeekFrom::Start(pos) => {
+                let extra_lines = pos % self.index.granularity;
+                let closest_index = pos - extra_lines;
+                self.seek_to_index(closest_index).await
+            },
+            SeekFrom::Current(pos) => {
+                let extra_lines = pos as u64 % self.index.granularity;
+                let extra_lines_from_current_pos = self.pos % self.index.granularity;
+                let previous_closest_index = self.pos - extra_lines_from_current_pos;
+                let closest_index = previous_closest_index + pos as u64 - extra_lines;
+                self.seek_to_index(closest_index).await
+            },
+            SeekFrom::End(pos) => {
+                let pos = self.line_count - pos.unsigned_abs();
+                let fut = Box::pin(self.seek_to_closest_index(SeekFrom::Start(pos)));
+                fut.await
+            }
+        }
+    }
+
+    /// Seeks to the given absolute line number, awaiting the underlying reader's
+    /// byte seeks and line reads. This is the async analogue of the blocking
+    /// `Seek` implementation driven by `SeekFrom::Start`.
+    pub async fn seek_to_line(&mut self, line: u64) -> Result<u64, Error> {
+        self.compute_index().await?;
+        let extra_lines = line % self.index.granularity;
+        let new_pos = self.seek_to_closest_index(SeekFrom::Start(line)).await?;
+        if extra_lines > 0 {
+            let extra_bytes = self.seek_forward(extra_lines).await?;
+            return Ok(new_pos + extra_bytes);
+        }
+        Ok(new_pos)
+    }
+}
+
+/// Line-indexed `AsyncSeek`. `poll_seek` interprets the `SeekFrom` offset as a
+/// line number (just like the blocking `Seek` impl), resolves the closest
+/// indexed anchor, awaits the underlying byte seek and then skips forward to the
+/// exact line by reading the buffered bytes, yielding `Poll::Pending` whenever
+/// the underlying reader would block. It relies on the index built by a previous
+/// `compute_index`/`seek_to_line`; without it the skip simply starts from the
+/// beginning of the file. The returned value is the resulting byte offset.
+impl<T: AsyncBufRead + AsyncSeek + Unpin> AsyncSeek for AsyncIndexedLineReader<T> {
+    fn poll_seek(self: Pin<&mut Self>, cx: &mut Context<'_>, pos: SeekFrom) -> Poll<Result<u64, Error>> {
+        let this = self.get_mut();
+        loop {
+            match this.seek_state {
+                SeekState::Idle => {
+                    let target = match pos {
+                        SeekFrom::Start(n) => n,
+                        SeekFrom::Current(n) => (this.pos as i64 + n).max(0) as u64,
+                        SeekFrom::End(n) => this.line_count.saturating_sub(n.unsigned_abs())
+                    };
+                    let extra = target % this.index.granularity;
+                    let closest = target - extra;
+                    let byte = this.index.byte_count_at_pos(&closest).unwrap_or(0);
+                    this.pos = closest;
+                    this.seek_state = SeekState::Seeking { byte, remaining: extra };
+                },
+                SeekState::Seeking { byte, remaining } => {
+                    match Pin::new(&mut this.reader).poll_seek(cx, SeekFrom::Start(byte)) {
+                        Poll::Ready(Ok(_)) => this.seek_state = SeekState::Skipping { byte, remaining },
+                        Poll::Ready(Err(err)) => {
+                            this.seek_state = SeekState::Idle;
+                            return Poll::Ready(Err(err));
+                        },
+                        Poll::Pending => return Poll::Pending
+                    }
+                },
+                SeekState::Skipping { byte, remaining } => {
+                    if remaining == 0 {
+                        this.seek_state = SeekState::Idle;
+                        return Poll::Ready(Ok(byte));
+                    }
+                    let (consumed, found_newline, eof) = {
+                        let buf = match Pin::new(&mut this.reader).poll_fill_buf(cx) {
+                            Poll::Ready(Ok(buf)) => buf,
+                            Poll::Ready(Err(err)) => {
+                                this.seek_state = SeekState::Idle;
+                                return Poll::Ready(Err(err));
+                            },
+                            Poll::Pending => return Poll::Pending
+                        };
+                        if buf.is_empty() {
+                            (0, false, true)
+                        } else if let Some(i) = buf.iter().position(|&b| b == b'\n') {
+                            (i + 1, true, false)
+                        } else {
+                            (buf.len(), false, false)
+                        }
+                    };
+                    if eof {
+                        this.seek_state = SeekState::Idle;
+                        return Poll::Ready(Ok(byte));
+                    }
+                    Pin::new(&mut this.reader).consume(consumed);
+                    let remaining = if found_newline {
+                        this.pos += 1;
+                        remaining - 1
+                    } else {
+                        remaining
+                    };
+                    this.seek_state = SeekState::Skipping { byte: byte + consumed as u64, remaining };
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures::executor::block_on;
+    use futures::io::{AsyncSeekExt, Cursor};
+
+    fn numbered_lines(count: u64) -> Vec<u8> {
+        let mut data = String::new();
+        for i in 0..count {
+            data.push_str(&i.to_string());
+            data.push('\n');
+        }
+        data.into_bytes()
+    }
+
+    #[test]
+    fn test_compute_index_resume() {
+        let mut reader = AsyncIndexedLineReader::new(Cursor::new(numbered_lines(250)), 100);
+
+        assert_eq!(block_on(reader.compute_index()).expect("Unable to compute index"), 250);
+
+        /* resuming from the existing anchor must not skip a line or undercount */
+        assert_eq!(block_on(reader.compute_index()).expect("Unable to compute index"), 250);
+        assert_eq!(reader.get_index().line_count(), 250);
+    }
+
+    #[test]
+    fn test_poll_seek_lands_on_line() {
+        let mut reader = AsyncIndexedLineReader::new(Cursor::new(numbered_lines(1000)), 100);
+        block_on(reader.compute_index()).expect("Unable to compute index");
+
+        /* both an anchored seek and one that skips extra lines land on the line */
+        block_on(reader.seek(SeekFrom::Start(450))).expect("Unable to seek");
+        let mut line = String::new();
+        block_on(reader.reader.read_line(&mut line)).expect("Unable to read line");
+        assert_eq!(line.trim(), "450");
+
+        block_on(reader.seek(SeekFrom::End(1))).expect("Unable to seek");
+        let mut line = String::new();
+        block_on(reader.reader.read_line(&mut line)).expect("Unable to read line");
+        assert_eq!(line.trim(), "999");
+    }
+}
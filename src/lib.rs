@@ -15,7 +15,6 @@
 //! ## Example
 //!
 //! ```no_run
-//! extern crate indexed_line_reader;
 //! # fn main() {
 //!
 //! use indexed_line_reader::*;
@@ -41,24 +40,58 @@
 //! # }
 //! ```
 
+#[cfg(feature = "async")]
+pub mod async_reader;
+
 use std::collections::BTreeMap;
-use std::io::{BufRead, Error, Read, Seek, SeekFrom};
+use std::io::{BufRead, Error, ErrorKind, Read, Seek, SeekFrom, Write};
+use std::sync::{Arc, RwLock};
+
+/// Magic header prefixing a serialized `LinesIndex`, followed by `INDEX_VERSION`.
+const INDEX_MAGIC: &[u8; 4] = b"ILRX";
+const INDEX_VERSION: u8 = 1;
+
+fn write_varint<W: Write>(w: &mut W, mut value: u64) -> Result<(), Error> {
+    loop {
+        let mut byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value != 0 { byte |= 0x80; }
+        w.write_all(&[byte])?;
+        if value == 0 { break }
+    }
+    Ok(())
+}
+
+fn read_varint<R: Read>(r: &mut R) -> Result<u64, Error> {
+    let mut result = 0u64;
+    let mut shift = 0;
+    loop {
+        let mut byte = [0u8; 1];
+        r.read_exact(&mut byte)?;
+        result |= ((byte[0] & 0x7f) as u64) << shift;
+        if byte[0] & 0x80 == 0 { break }
+        shift += 7;
+    }
+    Ok(result)
+}
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct LinesIndex {
     index: BTreeMap<u64, u64>,
     granularity: u64,
     line_count: u64,
-    byte_count: u64
+    byte_count: u64,
+    file_len: u64
 }
 
 impl LinesIndex {
     pub fn new(granularity: u64) -> LinesIndex {
         LinesIndex {
             index: BTreeMap::new(),
-            granularity: granularity,
+            granularity,
             line_count: 0,
-            byte_count: 0
+            byte_count: 0,
+            file_len: 0
         }
     }
 
@@ -79,26 +112,33 @@ impl LinesIndex {
     }
 
     pub fn byte_count_at_pos(&self, pos: &u64) -> Option<u64> {
-        self.index.get(pos).map(|&x| x)
+        self.index.get(pos).copied()
     }
 
     pub fn last_indexed_pos(&self) -> Option<u64> {
-        self.index.keys().map(|&x| x).max()
+        self.index.keys().copied().max()
     }
 
-    pub fn compute<T: BufRead + Seek>(&mut self, mut reader: &mut T) -> Result<u64, Error> {
+    /// Forces the next `compute` to re-scan the unindexed tail even when the file
+    /// length is unchanged, so appended lines are picked up.
+    pub fn invalidate(&mut self) {
+        self.file_len = 0;
+    }
+
+    pub fn compute<T: BufRead + Seek>(&mut self, reader: &mut T) -> Result<u64, Error> {
+        let file_len = reader.seek(SeekFrom::End(0))?;
+        if file_len == self.file_len {
+            return Ok(self.line_count);
+        }
         let initial_pos = self.last_indexed_pos().unwrap_or(0);
         let mut line_count = initial_pos;
         let mut byte_count = self.byte_count_at_pos(&line_count).unwrap_or(0);
-        try!(reader.seek(SeekFrom::Start(byte_count)));
-        if byte_count > 0 {
-            reader.lines().next();
-        }
+        reader.seek(SeekFrom::Start(byte_count))?;
         for (pos, line) in reader.lines().enumerate() {
             match line {
                 Ok(line) => {
-                    byte_count += line.as_bytes().len() as u64 + 1;
-                    if (pos as u64 + 1) % self.granularity == 0 {
+                    byte_count += line.len() as u64 + 1;
+                    if (pos as u64 + 1).is_multiple_of(self.granularity) {
                         self.insert(initial_pos + pos as u64 + 1, byte_count);
                     }
                     line_count += 1;
@@ -108,9 +148,68 @@ impl LinesIndex {
         }
         self.line_count = line_count;
         self.byte_count = byte_count;
+        self.file_len = file_len;
         Ok(line_count)
     }
 
+    /// Writes the index to `w` using a compact binary layout: a magic header and
+    /// version byte, the granularity, line count, byte count and indexed file
+    /// length as varints, then the delta-encoded `(pos, byte_count)` entries as
+    /// varints. The small deltas keep the encoding compact even for indexes over
+    /// 100M-line files. Persisting the real `file_len` lets a reloaded index skip
+    /// the `compute` scan when the file is unchanged.
+    pub fn serialize<W: Write>(&self, w: &mut W) -> Result<(), Error> {
+        w.write_all(INDEX_MAGIC)?;
+        w.write_all(&[INDEX_VERSION])?;
+        write_varint(w, self.granularity)?;
+        write_varint(w, self.line_count)?;
+        write_varint(w, self.byte_count)?;
+        write_varint(w, self.file_len)?;
+        write_varint(w, self.index.len() as u64)?;
+        let mut last_pos = 0u64;
+        for (&pos, &byte_count) in self.index.iter() {
+            write_varint(w, pos - last_pos)?;
+            write_varint(w, byte_count)?;
+            last_pos = pos;
+        }
+        Ok(())
+    }
+
+    /// Reads an index previously written by `serialize`. Rejects a buffer whose
+    /// magic header or version does not match, so stale or mismatched indexes are
+    /// not silently restored.
+    pub fn deserialize<R: Read>(r: &mut R) -> Result<LinesIndex, Error> {
+        let mut magic = [0u8; 4];
+        r.read_exact(&mut magic)?;
+        if &magic != INDEX_MAGIC {
+            return Err(Error::new(ErrorKind::InvalidData, "invalid lines index magic header"));
+        }
+        let mut version = [0u8; 1];
+        r.read_exact(&mut version)?;
+        if version[0] != INDEX_VERSION {
+            return Err(Error::new(ErrorKind::InvalidData, "unsupported lines index version"));
+        }
+        let granularity = read_varint(r)?;
+        let line_count = read_varint(r)?;
+        let byte_count = read_varint(r)?;
+        let file_len = read_varint(r)?;
+        let entries = read_varint(r)?;
+        let mut index = BTreeMap::new();
+        let mut last_pos = 0u64;
+        for _ in 0..entries {
+            last_pos += read_varint(r)?;
+            let pos_byte_count = read_varint(r)?;
+            index.insert(last_pos, pos_byte_count);
+        }
+        Ok(LinesIndex {
+            index,
+            granularity,
+            line_count,
+            byte_count,
+            file_len
+        })
+    }
+
     pub fn get_ref(&self) -> &BTreeMap<u64, u64> {
         &self.index
     }
@@ -126,39 +225,136 @@ impl LinesIndex {
 
 #[derive(Debug)]
 pub struct IndexedLineReader<T> {
-    index: LinesIndex,
+    index: Arc<RwLock<LinesIndex>>,
     pos: u64,
     line_count: u64,
+    start_line: u64,
+    end_line: Option<u64>,
     reader: T
 }
 
 impl<T: BufRead + Seek> IndexedLineReader<T> {
     pub fn new(reader: T, index_granularity: u64) -> IndexedLineReader<T> {
         IndexedLineReader {
-            index: LinesIndex::new(index_granularity),
+            index: Arc::new(RwLock::new(LinesIndex::new(index_granularity))),
             pos: 0,
             line_count: 0,
-            reader: reader
+            start_line: 0,
+            end_line: None,
+            reader
         }
     }
 
-    pub fn get_index(&self) -> &LinesIndex {
-        &self.index
+    /// Creates a cursor sharing a `LinesIndex` with other cursors over the same
+    /// file. Each cursor keeps its own `pos` and positioned reads, so several
+    /// consumers can seek to different lines concurrently without stepping on each
+    /// other's file offset, while the index is built only once. The index lives
+    /// behind an `RwLock`, so a `refresh` (or append pick-up) on any cursor is seen
+    /// by all of them; only the per-cursor `line_count` cache is refreshed lazily on
+    /// the next `compute_index`/`seek`.
+    pub fn with_shared_index(reader: T, index: Arc<RwLock<LinesIndex>>) -> IndexedLineReader<T> {
+        let line_count = index.read().unwrap().line_count();
+        IndexedLineReader {
+            index,
+            pos: 0,
+            line_count,
+            start_line: 0,
+            end_line: None,
+            reader
+        }
+    }
+
+    /// Creates a reader that exposes only the lines in `[start_line, end_line)`,
+    /// pretending lines outside the window do not exist. `SeekFrom::Start(n)` maps
+    /// to the absolute line `start_line + n`, `SeekFrom::End(n)` is measured from
+    /// `end_line` (or real EOF when unbounded), and `line_count` reports the clamped
+    /// window size. The underlying `LinesIndex` is still computed over the whole
+    /// file; only the logical line numbers are translated. The bounding applies to
+    /// seek translation only: `Read`/`BufRead` still stream past `end_line` to real
+    /// EOF, so callers that must not read beyond the window should stop after
+    /// `line_count` lines themselves.
+    pub fn new_bounded(reader: T, index_granularity: u64, start_line: u64, end_line: Option<u64>) -> IndexedLineReader<T> {
+        IndexedLineReader {
+            index: Arc::new(RwLock::new(LinesIndex::new(index_granularity))),
+            pos: 0,
+            line_count: 0,
+            start_line,
+            end_line,
+            reader
+        }
+    }
+
+    /// The absolute line number at which the window ends (exclusive), clamped to
+    /// the real line count of the file.
+    fn window_end(&self) -> u64 {
+        match self.end_line {
+            Some(end_line) if end_line < self.line_count => end_line,
+            _ => self.line_count
+        }
     }
 
-    pub fn restore_index(&mut self, index: LinesIndex) {
-        self.index = index;
+    /// The number of lines visible through this reader's window.
+    pub fn line_count(&self) -> u64 {
+        self.window_end().saturating_sub(self.start_line)
+    }
+
+    /// Returns a snapshot clone of the current index. It is a copy rather than a
+    /// borrow because the index is shared behind a lock.
+    pub fn get_index(&self) -> LinesIndex {
+        self.index.read().unwrap().clone()
+    }
+
+    /// A clonable handle to the shared index, for building further cursors with
+    /// `with_shared_index`.
+    pub fn shared_index(&self) -> Arc<RwLock<LinesIndex>> {
+        self.index.clone()
+    }
+
+    /// The index granularity, read from the shared index.
+    fn granularity(&self) -> u64 {
+        self.index.read().unwrap().granularity
+    }
+
+    /// Replaces the contents of the shared index with `index`, e.g. one loaded via
+    /// `LinesIndex::deserialize`. A stale index built with a different granularity
+    /// than this reader was configured for is rejected with `ErrorKind::InvalidData`
+    /// rather than silently adopted, since the seek anchors would no longer line up.
+    /// The write goes through the shared lock, so cursors built with
+    /// `with_shared_index` observe the restored index too.
+    pub fn restore_index(&mut self, index: LinesIndex) -> Result<(), Error> {
+        if index.granularity != self.granularity() {
+            return Err(Error::new(ErrorKind::InvalidData, "lines index granularity mismatch"));
+        }
+        *self.index.write().unwrap() = index;
+        Ok(())
     }
 
     pub fn compute_index(&mut self) -> Result<u64, Error> {
-        self.index.compute(&mut self.reader).and_then(|line_count| {
-            self.line_count = line_count;
-            Ok(line_count)
-        })
+        let file_len = self.reader.seek(SeekFrom::End(0))?;
+        {
+            let index = self.index.read().unwrap();
+            if file_len == index.file_len {
+                self.line_count = index.line_count;
+                return Ok(self.line_count);
+            }
+        }
+        let line_count = self.index.write().unwrap().compute(&mut self.reader)?;
+        self.line_count = line_count;
+        Ok(line_count)
     }
 
     pub fn clear_index(&mut self) {
-        self.index.clear()
+        self.index.write().unwrap().clear()
+    }
+
+    /// Re-scans the unindexed tail of the file to pick up lines appended since the
+    /// last `compute_index`/`seek`. Unlike a plain `compute_index`, this forces the
+    /// scan even when the file length appears unchanged. Because the index is
+    /// shared behind a lock, other cursors built with `with_shared_index` observe
+    /// the updated index too.
+    pub fn refresh(&mut self) -> Result<u64, Error> {
+        self.index.write().unwrap().invalidate();
+        self.compute_index()
     }
 
     pub fn get_current_pos(&self) -> u64 {
@@ -171,26 +367,26 @@ impl<T: BufRead + Seek> IndexedLineReader<T> {
 
     fn seek_to_index(&mut self, indexed_pos: u64) -> Result<u64, Error> {
         self.pos = indexed_pos;
-        let byte_count = self.index.byte_count_at_pos(&indexed_pos).unwrap_or(0);
+        let byte_count = self.index.read().unwrap().byte_count_at_pos(&indexed_pos).unwrap_or(0);
         self.reader.seek(SeekFrom::Start(byte_count))
     }
 
     fn seek_to_closest_index(&mut self, pos: SeekFrom) -> Result<u64, Error> {
         match pos {
             SeekFrom::Start(pos) => {
-                let extra_lines = pos % self.index.granularity;
+                let extra_lines = pos % self.granularity();
                 let closest_index = pos - extra_lines;
                 self.seek_to_index(closest_index)
             },
             SeekFrom::Current(pos) => {
-                let extra_lines = pos as u64 % self.index.granularity;
-                let extra_lines_from_current_pos = self.pos % self.index.granularity;
+                let extra_lines = pos as u64 % self.granularity();
+                let extra_lines_from_current_pos = self.pos % self.granularity();
                 let previous_closest_index = self.pos - extra_lines_from_current_pos;
                 let closest_index = previous_closest_index + pos as u64 - extra_lines;
                 self.seek_to_index(closest_index)
             },
             SeekFrom::End(pos) => {
-                let pos = self.line_count - pos.abs() as u64;
+                let pos = self.line_count - pos.unsigned_abs();
                 self.seek_to_closest_index(SeekFrom::Start(pos))
             }
         }
@@ -204,7 +400,7 @@ impl<T: BufRead + Seek> IndexedLineReader<T> {
                 Ok(line) => {
                     lines_left -= 1;
                     self.pos += 1;
-                    extra_byte_count += line.as_bytes().len() as u64 + 1;
+                    extra_byte_count += line.len() as u64 + 1;
                     if lines_left == 0 { break }
                 },
                 Err(err) => return Err(err)
@@ -212,6 +408,63 @@ impl<T: BufRead + Seek> IndexedLineReader<T> {
         }
         Ok(extra_byte_count)
     }
+
+    /// Seeks `lines` forward (or backward) from the current line. When the target
+    /// stays within the granularity block of the current position, it re-anchors to
+    /// that block's index entry and skips forward to the target, an O(granularity)
+    /// path that avoids re-scanning from the closest index of a far-away line. This
+    /// re-anchoring makes it independent of the reader's current byte offset, so it
+    /// is robust even when no positioning `seek` preceded it. Any other target falls
+    /// back to the full index-anchored `seek`.
+    ///
+    /// Note that `lines` are counted in absolute-line space: unlike `seek`, this
+    /// does not translate through a `new_bounded` window's `start_line`, so on a
+    /// bounded reader it moves relative to the current absolute position.
+    pub fn seek_relative(&mut self, lines: i64) -> Result<u64, Error> {
+        if lines == 0 {
+            return self.reader.stream_position();
+        }
+        let target = (self.pos as i64 + lines).max(0) as u64;
+        let current_anchor = self.pos - (self.pos % self.granularity());
+        let next_anchor = current_anchor + self.granularity();
+        if lines > 0 && target < next_anchor {
+            self.seek_to_index(current_anchor)?;
+            self.seek_forward(target - current_anchor)?;
+            self.reader.stream_position()
+        } else {
+            self.seek_absolute(SeekFrom::Start(target))
+        }
+    }
+
+    /// Reads the last `n` lines of the file without touching the `LinesIndex`.
+    ///
+    /// Seeks to EOF and reads fixed-size blocks backward from the end, counting
+    /// newlines until at least `n` lines are available or the start of the file is
+    /// reached, then splits the accumulated tail region and returns its final `n`
+    /// lines. A file not ending in a trailing newline still counts its final partial
+    /// line, and lines split across block boundaries are stitched back together. If
+    /// `n` is larger than the total line count every line is returned.
+    pub fn tail(&mut self, n: u64) -> Result<Vec<String>, Error> {
+        const BLOCK_SIZE: u64 = 4096;
+        let file_size = self.reader.seek(SeekFrom::End(0))?;
+        let mut buf: Vec<u8> = Vec::new();
+        let mut pos = file_size;
+        let mut newline_count = 0u64;
+        while pos > 0 && newline_count <= n {
+            let read_size = if pos < BLOCK_SIZE { pos } else { BLOCK_SIZE };
+            pos -= read_size;
+            self.reader.seek(SeekFrom::Start(pos))?;
+            let mut block = vec![0u8; read_size as usize];
+            self.reader.read_exact(&mut block)?;
+            newline_count += block.iter().filter(|&&b| b == b'\n').count() as u64;
+            block.extend_from_slice(&buf);
+            buf = block;
+        }
+        let text = String::from_utf8_lossy(&buf);
+        let lines: Vec<String> = text.lines().map(|line| line.to_string()).collect();
+        let start = if lines.len() as u64 > n { lines.len() - n as usize } else { 0 };
+        Ok(lines[start..].to_vec())
+    }
 }
 
 impl<T: Read> Read for IndexedLineReader<T> {
@@ -229,15 +482,15 @@ impl<T: BufRead> BufRead for IndexedLineReader<T> {
     }
 }
 
-impl<T: BufRead + Seek> Seek for IndexedLineReader<T> {
-    fn seek(&mut self, pos: SeekFrom) -> Result<u64, Error> {
+impl<T: BufRead + Seek> IndexedLineReader<T> {
+    fn seek_absolute(&mut self, pos: SeekFrom) -> Result<u64, Error> {
         self.compute_index().and_then(|_| {
             match pos {
                 SeekFrom::Start(pos) => {
-                    let extra_lines = pos as u64 % self.index.granularity;
+                    let extra_lines = pos % self.granularity();
                     self.seek_to_closest_index(SeekFrom::Start(pos)).and_then(|new_pos| {
                         if extra_lines > 0 {
-                            self.seek(SeekFrom::Current(extra_lines as i64))
+                            self.seek_absolute(SeekFrom::Current(extra_lines as i64))
                         } else {
                             Ok(new_pos)
                         }
@@ -245,8 +498,8 @@ impl<T: BufRead + Seek> Seek for IndexedLineReader<T> {
                 },
                 SeekFrom::Current(pos) => {
                     if pos >= 0 {
-                        let extra_lines = pos as u64 % self.index.granularity;
-                        let extra_lines_from_current_pos = self.pos % self.index.granularity;
+                        let extra_lines = pos as u64 % self.granularity();
+                        let extra_lines_from_current_pos = self.pos % self.granularity();
                         self.seek_to_closest_index(SeekFrom::Current(pos)).and_then(|new_pos| {
                             if extra_lines + extra_lines_from_current_pos > 0 {
                                 self.seek_forward(extra_lines + extra_lines_from_current_pos)
@@ -255,19 +508,44 @@ impl<T: BufRead + Seek> Seek for IndexedLineReader<T> {
                             }
                         })
                     } else {
-                        let pos = self.pos - pos.abs() as u64;
-                        self.seek(SeekFrom::Start(pos))
+                        let pos = self.pos - pos.unsigned_abs();
+                        self.seek_absolute(SeekFrom::Start(pos))
                     }
                 },
                 SeekFrom::End(pos) => {
-                    let pos = self.line_count - pos.abs() as u64;
-                    self.seek(SeekFrom::Start(pos))
+                    let pos = self.line_count - pos.unsigned_abs();
+                    self.seek_absolute(SeekFrom::Start(pos))
                 }
             }
         })
     }
 }
 
+impl<T: BufRead + Seek> Seek for IndexedLineReader<T> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, Error> {
+        self.compute_index().and_then(|_| {
+            let absolute = match pos {
+                SeekFrom::Start(pos) => {
+                    let absolute = self.start_line + pos;
+                    if absolute >= self.window_end() {
+                        return Err(Error::new(ErrorKind::InvalidInput, "seek past the end of the window"));
+                    }
+                    SeekFrom::Start(absolute)
+                },
+                SeekFrom::Current(pos) => SeekFrom::Current(pos),
+                SeekFrom::End(pos) => {
+                    let offset = pos.unsigned_abs();
+                    if offset > self.line_count() {
+                        return Err(Error::new(ErrorKind::InvalidInput, "seek past the start of the window"));
+                    }
+                    SeekFrom::Start(self.window_end() - offset)
+                }
+            };
+            self.seek_absolute(absolute)
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -275,9 +553,14 @@ mod tests {
     use std::fs::*;
     use std::io::{BufRead, BufReader, Seek, SeekFrom, Write};
 
+    /* Opens (creating if needed) an append-mode writer for a test log fixture. */
+    fn create_log(log_name: &str) -> File {
+        OpenOptions::new().create(true).append(true).open(log_name).expect("Unable to open file writer")
+    }
+
     fn seek_and_assert_line_number(mut reader: &mut IndexedLineReader<BufReader<File>>,
                                     seek_from: SeekFrom, expected_line_number: u64) {
-        reader.seek(seek_from).expect(&format!("Unable to seek from {:?}", seek_from));
+        reader.seek(seek_from).unwrap_or_else(|_| panic!("Unable to seek from {:?}", seek_from));
         let line = (&mut reader).lines().next().unwrap().unwrap();
         let line_number: u64 = line.parse().expect("Unable to deserialize line number");
         assert_eq!(line_number, expected_line_number);
@@ -286,14 +569,14 @@ mod tests {
     #[test]
     fn test_seek() {
         let log_name = "indexed-line-reader.log";
-        let mut file_writer = OpenOptions::new().create(true).write(true).append(true).open(log_name).expect("Unable to open file writer");
+        let mut file_writer = create_log(log_name);
 
         for i in 0..10000 {
-            assert!(write!(file_writer, "{}\n", i).is_ok());
+            assert!(writeln!(file_writer, "{}", i).is_ok());
         }
 
         let file_reader = OpenOptions::new().read(true).open(log_name).expect("Unable to open file reader");
-        let mut line_reader = &mut IndexedLineReader::new(BufReader::new(file_reader), 100);
+        let line_reader = &mut IndexedLineReader::new(BufReader::new(file_reader), 100);
 
         line_reader.compute_index().expect("Unable to compute index");
 
@@ -307,4 +590,208 @@ mod tests {
 
         remove_file(log_name).expect("Unable to delete log");
     }
+
+    fn assert_next_line_number(reader: &mut IndexedLineReader<BufReader<File>>, expected: u64) {
+        let line = reader.lines().next().unwrap().unwrap();
+        let line_number: u64 = line.parse().expect("Unable to deserialize line number");
+        assert_eq!(line_number, expected);
+    }
+
+    #[test]
+    fn test_seek_relative() {
+        let log_name = "indexed-line-reader-relative.log";
+        let mut file_writer = create_log(log_name);
+
+        for i in 0..10000 {
+            assert!(writeln!(file_writer, "{}", i).is_ok());
+        }
+
+        let file_reader = OpenOptions::new().read(true).open(log_name).expect("Unable to open file reader");
+        let mut line_reader = IndexedLineReader::new(BufReader::new(file_reader), 100);
+        line_reader.compute_index().expect("Unable to compute index");
+
+        /* the fast path re-anchors, so it is correct straight after compute_index
+           with no positioning seek (which leaves the reader at EOF) */
+        line_reader.seek_relative(5).expect("Unable to seek relative");
+        assert_next_line_number(&mut line_reader, 5);
+
+        line_reader.seek(SeekFrom::Start(120)).expect("Unable to seek");
+
+        /* within the current granularity block: the buffer-local fast path */
+        line_reader.seek_relative(30).expect("Unable to seek relative");
+        assert_next_line_number(&mut line_reader, 150);
+
+        /* across an anchor: falls back to the index-anchored seek */
+        line_reader.seek(SeekFrom::Start(150)).expect("Unable to seek");
+        line_reader.seek_relative(100).expect("Unable to seek relative");
+        assert_next_line_number(&mut line_reader, 250);
+
+        /* backward also falls back to the index-anchored seek */
+        line_reader.seek(SeekFrom::Start(250)).expect("Unable to seek");
+        line_reader.seek_relative(-30).expect("Unable to seek relative");
+        assert_next_line_number(&mut line_reader, 220);
+
+        remove_file(log_name).expect("Unable to delete log");
+    }
+
+    #[test]
+    fn test_refresh_noop_preserves_line_count() {
+        let log_name = "indexed-line-reader-refresh.log";
+        let mut file_writer = create_log(log_name);
+
+        /* 250 lines, no trailing newline */
+        for i in 0..250 {
+            if i > 0 { assert!(writeln!(file_writer).is_ok()); }
+            assert!(write!(file_writer, "{}", i).is_ok());
+        }
+
+        let file_reader = OpenOptions::new().read(true).open(log_name).expect("Unable to open file reader");
+        let mut line_reader = IndexedLineReader::new(BufReader::new(file_reader), 100);
+
+        assert_eq!(line_reader.compute_index().expect("Unable to compute index"), 250);
+
+        /* refreshing an unchanged file must not drop the last (unterminated) line */
+        assert_eq!(line_reader.refresh().expect("Unable to refresh index"), 250);
+        assert_eq!(line_reader.get_index().line_count(), 250);
+
+        remove_file(log_name).expect("Unable to delete log");
+    }
+
+    #[test]
+    fn test_serialize_deserialize_round_trip() {
+        let log_name = "indexed-line-reader-serde.log";
+        let mut file_writer = create_log(log_name);
+
+        /* 250 lines, no trailing newline, so byte_count != file_len */
+        for i in 0..250 {
+            if i > 0 { assert!(writeln!(file_writer).is_ok()); }
+            assert!(write!(file_writer, "{}", i).is_ok());
+        }
+
+        let file_reader = OpenOptions::new().read(true).open(log_name).expect("Unable to open file reader");
+        let mut line_reader = IndexedLineReader::new(BufReader::new(file_reader), 100);
+        line_reader.compute_index().expect("Unable to compute index");
+
+        let index = line_reader.get_index().clone();
+        let mut buf: Vec<u8> = Vec::new();
+        index.serialize(&mut buf).expect("Unable to serialize index");
+        let restored = LinesIndex::deserialize(&mut &buf[..]).expect("Unable to deserialize index");
+        assert_eq!(index, restored);
+
+        /* restoring the index on the unchanged file must skip the compute scan */
+        let file_reader = OpenOptions::new().read(true).open(log_name).expect("Unable to open file reader");
+        let mut line_reader = IndexedLineReader::new(BufReader::new(file_reader), 100);
+        line_reader.restore_index(restored.clone()).expect("Unable to restore index");
+        assert_eq!(line_reader.compute_index().expect("Unable to compute index"), 250);
+
+        /* an index built with a different granularity is rejected */
+        let file_reader = OpenOptions::new().read(true).open(log_name).expect("Unable to open file reader");
+        let mut other = IndexedLineReader::new(BufReader::new(file_reader), 50);
+        assert!(other.restore_index(restored).is_err());
+
+        remove_file(log_name).expect("Unable to delete log");
+    }
+
+    #[test]
+    fn test_shared_index_cursors_stay_in_sync_after_refresh() {
+        let log_name = "indexed-line-reader-shared.log";
+        let mut file_writer = create_log(log_name);
+
+        for i in 0..150 {
+            assert!(writeln!(file_writer, "{}", i).is_ok());
+        }
+
+        let file_reader = OpenOptions::new().read(true).open(log_name).expect("Unable to open file reader");
+        let mut first = IndexedLineReader::new(BufReader::new(file_reader), 100);
+        first.compute_index().expect("Unable to compute index");
+
+        /* a second cursor shares the same index */
+        let file_reader = OpenOptions::new().read(true).open(log_name).expect("Unable to open file reader");
+        let second = IndexedLineReader::with_shared_index(BufReader::new(file_reader), first.shared_index());
+        assert_eq!(second.get_index().line_count(), 150);
+
+        /* append lines and refresh through the first cursor */
+        for i in 150..200 {
+            assert!(writeln!(file_writer, "{}", i).is_ok());
+        }
+        assert_eq!(first.refresh().expect("Unable to refresh index"), 200);
+
+        /* the second cursor observes the update through the shared index */
+        assert_eq!(first.get_index().line_count(), 200);
+        assert_eq!(second.get_index().line_count(), 200);
+
+        remove_file(log_name).expect("Unable to delete log");
+    }
+
+    #[test]
+    fn test_new_bounded() {
+        let log_name = "indexed-line-reader-bounded.log";
+        let mut file_writer = create_log(log_name);
+
+        for i in 0..1000 {
+            assert!(writeln!(file_writer, "{}", i).is_ok());
+        }
+
+        let file_reader = OpenOptions::new().read(true).open(log_name).expect("Unable to open file reader");
+        let mut line_reader = IndexedLineReader::new_bounded(BufReader::new(file_reader), 100, 200, Some(500));
+
+        line_reader.compute_index().expect("Unable to compute index");
+
+        /* only the [200, 500) window is visible */
+        assert_eq!(line_reader.line_count(), 300);
+
+        seek_and_assert_line_number(&mut line_reader, SeekFrom::Start(0), 200);
+        seek_and_assert_line_number(&mut line_reader, SeekFrom::Start(50), 250);
+        seek_and_assert_line_number(&mut line_reader, SeekFrom::Start(250), 450);
+        seek_and_assert_line_number(&mut line_reader, SeekFrom::End(1), 499);
+        seek_and_assert_line_number(&mut line_reader, SeekFrom::End(300), 200);
+
+        /* seeks outside the window are rejected, never reading past either bound */
+        assert!(line_reader.seek(SeekFrom::Start(300)).is_err());
+        assert!(line_reader.seek(SeekFrom::Start(400)).is_err());
+        assert!(line_reader.seek(SeekFrom::End(301)).is_err());
+        assert!(line_reader.seek(SeekFrom::End(350)).is_err());
+
+        remove_file(log_name).expect("Unable to delete log");
+    }
+
+    #[test]
+    fn test_tail() {
+        let log_name = "indexed-line-reader-tail.log";
+        let mut file_writer = create_log(log_name);
+
+        for i in 0..1000 {
+            assert!(writeln!(file_writer, "{}", i).is_ok());
+        }
+
+        let file_reader = OpenOptions::new().read(true).open(log_name).expect("Unable to open file reader");
+        let mut line_reader = IndexedLineReader::new(BufReader::new(file_reader), 100);
+
+        assert_eq!(line_reader.tail(3).expect("Unable to tail"), vec!["997", "998", "999"]);
+
+        /* n larger than the total line count returns every line */
+        let all = line_reader.tail(5000).expect("Unable to tail");
+        assert_eq!(all.len(), 1000);
+        assert_eq!(all[0], "0");
+        assert_eq!(all[999], "999");
+
+        remove_file(log_name).expect("Unable to delete log");
+    }
+
+    #[test]
+    fn test_tail_without_trailing_newline() {
+        let log_name = "indexed-line-reader-tail-partial.log";
+        let mut file_writer = create_log(log_name);
+
+        assert!(write!(file_writer, "a\nb\nc").is_ok());
+
+        let file_reader = OpenOptions::new().read(true).open(log_name).expect("Unable to open file reader");
+        let mut line_reader = IndexedLineReader::new(BufReader::new(file_reader), 100);
+
+        /* the final unterminated line still counts */
+        assert_eq!(line_reader.tail(2).expect("Unable to tail"), vec!["b", "c"]);
+        assert_eq!(line_reader.tail(10).expect("Unable to tail"), vec!["a", "b", "c"]);
+
+        remove_file(log_name).expect("Unable to delete log");
+    }
 }